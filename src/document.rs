@@ -7,14 +7,23 @@
  * ropes or rrb-trees).
  */
 
+use crate::highlight::Highlighter;
 use crate::{Position, Row};
 use std::fs;
 use std::io::Write;
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
 
 pub struct Document {
     rows: Vec<Row>,
     filename: Option<String>,
     dirty: bool,
+    highlighter: Highlighter,
 }
 
 impl Document {
@@ -23,10 +32,12 @@ impl Document {
             rows: vec![Row::default()],
             filename: None,
             dirty: false,
+            highlighter: Highlighter::for_extension(""),
         }
     }
 
     pub fn open(filename: &str) -> Self {
+        let highlighter = Highlighter::for_extension(extension(filename));
         let contents = fs::read_to_string(filename);
 
         if contents.is_err() {
@@ -34,18 +45,21 @@ impl Document {
                 rows: Vec::new(),
                 filename: Some(filename.to_string()),
                 dirty: false,
+                highlighter,
             };
         }
 
         let mut rows = Vec::new();
         for value in contents.unwrap().lines() {
-            let row = Row::from(value);
+            let mut row = Row::from(value);
+            row.highlight(&highlighter);
             rows.push(row);
         }
         Self {
             rows,
             filename: Some(filename.to_string()),
             dirty: false,
+            highlighter,
         }
     }
 
@@ -85,6 +99,18 @@ impl Document {
         Ok(())
     }
 
+    /// Saves to `filename`, adopting it as the document's filename (and
+    /// re-deriving the highlighter from its extension) even if the buffer
+    /// was opened under a different name or no name at all.
+    pub fn save_as(&mut self, filename: &str) -> Result<(), std::io::Error> {
+        self.filename = Some(filename.to_string());
+        self.highlighter = Highlighter::for_extension(extension(filename));
+        for row in &mut self.rows {
+            row.highlight(&self.highlighter);
+        }
+        self.save()
+    }
+
     pub fn insert(&mut self, at: &Position, c: char) {
         if at.y > self.len() {
             return;
@@ -98,6 +124,7 @@ impl Document {
             let row = &mut self.rows[at.y];
             row.insert(at.x, c);
         }
+        self.rehighlight(at.y);
     }
 
     pub fn insert_newline(&mut self, at: &Position) {
@@ -107,10 +134,13 @@ impl Document {
         self.dirty = true;
         if at.y == self.len() {
             self.rows.push(Row::default());
+            self.rehighlight(at.y);
         } else {
             let row = &mut self.rows[at.y];
             let new_row = row.split(at.x);
             self.rows.insert(at.y.saturating_add(1), new_row);
+            self.rehighlight(at.y);
+            self.rehighlight(at.y.saturating_add(1));
         }
     }
 
@@ -128,5 +158,61 @@ impl Document {
             let row = &mut self.rows[at.y];
             row.delete(at.x);
         }
+        self.rehighlight(at.y);
     }
+
+    fn rehighlight(&mut self, index: usize) {
+        if let Some(row) = self.rows.get_mut(index) {
+            row.highlight(&self.highlighter);
+        }
+    }
+
+    /// Scans forward or backward from `after`, wrapping around the document,
+    /// for the next occurrence of `query`.
+    pub fn find(&self, query: &str, after: &Position, direction: Direction) -> Option<Position> {
+        if query.is_empty() || self.rows.is_empty() {
+            return None;
+        }
+
+        let num_rows = self.rows.len();
+        let mut y = after.y.min(num_rows.saturating_sub(1));
+        let mut x_start = Some(after.x);
+
+        for _ in 0..=num_rows {
+            if let Some(row) = self.rows.get(y) {
+                let at = x_start.take().unwrap_or(match direction {
+                    Direction::Forward => 0,
+                    Direction::Backward => row.len(),
+                });
+                if let Some(x) = row.find(query, at, direction) {
+                    return Some(Position { x, y });
+                }
+            }
+            y = match direction {
+                Direction::Forward => {
+                    if y.saturating_add(1) == num_rows {
+                        0
+                    } else {
+                        y + 1
+                    }
+                }
+                Direction::Backward => {
+                    if y == 0 {
+                        num_rows - 1
+                    } else {
+                        y - 1
+                    }
+                }
+            };
+        }
+
+        None
+    }
+}
+
+fn extension(filename: &str) -> &str {
+    Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
 }
@@ -2,17 +2,32 @@
  * editor.rs contains the source code for the editor representation in jot
  */
 
-use crate::{Document, Row, Terminal};
+use crate::{Direction, Document, Row, Screen, Terminal, Theme};
 use std::env;
+use std::time::{Duration, Instant};
 use termion::color;
 use termion::event::Key;
+use unicode_segmentation::UnicodeSegmentation;
 
 const EDITOR_NAME: &str = "Jot";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const AUTHOR: &str = "by @aneekm";
 const SCROLLOFF: usize = 5;
-const STATUS_FG_COLOR: color::Rgb = color::Rgb(136, 0, 26);
-const STATUS_BG_COLOR: color::Rgb = color::Rgb(230, 233, 236);
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(5);
+
+struct StatusMessage {
+    text: String,
+    time: Instant,
+}
+
+impl StatusMessage {
+    fn new(text: String) -> Self {
+        Self {
+            text,
+            time: Instant::now(),
+        }
+    }
+}
 
 #[derive(Default, Clone)]
 pub struct Position {
@@ -25,20 +40,29 @@ enum Mode {
     Normal,
     Insert,
     Command,
+    Search,
 }
 
 pub struct Editor {
     quit: bool,
     mode: Mode,
     terminal: Terminal,
+    screen: Screen,
     document: Document,
     cursor: Position,
     scroll_offset: Position,
+    theme: Theme,
+    search_query: String,
+    search_origin: Option<Position>,
+    current_match: Option<Position>,
+    command_buffer: String,
+    status_message: Option<StatusMessage>,
 }
 
 impl Editor {
     pub fn run(&mut self) {
         loop {
+            self.check_resize();
             if let Err(e) = self.refresh_screen() {
                 die(e);
             }
@@ -51,6 +75,19 @@ impl Editor {
         }
     }
 
+    /// Polls the terminal's dimensions once per loop iteration and, if the
+    /// window was resized, resizes the back buffer (forcing a full repaint)
+    /// and re-clamps the scroll offset against the new viewport. The cursor
+    /// itself is document-space (row/grapheme index), not viewport-space, so
+    /// it stays valid across a resize without a separate clamp; `scroll()`
+    /// keeps it inside the visible window via the usual scroll-off logic.
+    fn check_resize(&mut self) {
+        if let Ok(true) = self.terminal.refresh_size() {
+            self.screen.resize(self.terminal.size().height as usize + 2);
+            self.scroll();
+        }
+    }
+
     pub fn default() -> Self {
         let args: Vec<String> = env::args().collect();
         let document = if let Some(filename) = args.get(1) {
@@ -59,17 +96,27 @@ impl Editor {
             Document::default()
         };
 
+        let terminal = Terminal::default().expect("Failed to initialize terminal.");
+        let screen = Screen::new(terminal.size().height as usize + 2);
+
         Self {
             quit: false,
             mode: Mode::Normal,
-            terminal: Terminal::default().expect("Failed to initialize terminal."),
+            terminal,
+            screen,
             document,
             cursor: Position::default(),
             scroll_offset: Position::default(),
+            theme: Theme::load(),
+            search_query: String::new(),
+            search_origin: None,
+            current_match: None,
+            command_buffer: String::new(),
+            status_message: None,
         }
     }
 
-    fn refresh_screen(&self) -> Result<(), std::io::Error> {
+    fn refresh_screen(&mut self) -> Result<(), std::io::Error> {
         Terminal::cursor_visible(false);
         Terminal::cursor_position(&Position::default());
 
@@ -77,10 +124,10 @@ impl Editor {
             Terminal::clear_screen();
             println!("Thanks for using jot!\r");
         } else {
-            self.draw_lines();
-            self.draw_status_bar();
+            self.build_frame();
+            self.screen.present()?;
             Terminal::cursor_position(&Position {
-                x: self.cursor.x.saturating_sub(self.scroll_offset.x),
+                x: self.cursor_column().saturating_sub(self.scroll_offset.x),
                 y: self.cursor.y.saturating_sub(self.scroll_offset.y),
             });
         }
@@ -91,6 +138,18 @@ impl Editor {
 
     fn handle_keypress(&mut self) -> Result<(), std::io::Error> {
         let pressed_key = Terminal::read_key()?;
+
+        if self.mode == Mode::Search {
+            self.handle_search_keypress(pressed_key);
+            self.scroll();
+            return Ok(());
+        }
+
+        if self.mode == Mode::Command {
+            self.handle_command_keypress(pressed_key);
+            return Ok(());
+        }
+
         match pressed_key {
             Key::Char('\n') => {
                 self.document.insert_newline(&self.cursor);
@@ -98,7 +157,13 @@ impl Editor {
             Key::Char(c) => match self.mode {
                 Mode::Normal => match c {
                     'i' => self.mode = Mode::Insert,
-                    ':' => self.mode = Mode::Command,
+                    ':' => {
+                        self.command_buffer.clear();
+                        self.mode = Mode::Command;
+                    }
+                    '/' => self.start_search(),
+                    'n' => self.jump_to_match(Direction::Forward),
+                    'N' => self.jump_to_match(Direction::Backward),
                     _ => (),
                 },
                 Mode::Insert => {
@@ -112,9 +177,6 @@ impl Editor {
                 }
                 _ => (),
             },
-            Key::Ctrl('q') => {
-                self.quit = true; // TODO: replace this with real :w :q command mode ops
-            }
             Key::Delete => self.document.delete(&self.cursor),
             Key::Backspace => {
                 if self.cursor.x > 0 || self.cursor.y > 0 {
@@ -136,6 +198,161 @@ impl Editor {
         Ok(())
     }
 
+    fn start_search(&mut self) {
+        self.search_origin = Some(self.cursor.clone());
+        self.search_query.clear();
+        self.current_match = None;
+        self.mode = Mode::Search;
+    }
+
+    fn handle_search_keypress(&mut self, key: Key) {
+        match key {
+            Key::Char('\n') => self.mode = Mode::Normal,
+            Key::Esc => {
+                if let Some(origin) = self.search_origin.take() {
+                    self.cursor = origin;
+                }
+                self.search_query.clear();
+                self.current_match = None;
+                self.mode = Mode::Normal;
+            }
+            Key::Backspace => {
+                self.search_query.pop();
+                self.update_search();
+            }
+            Key::Char(c) => {
+                self.search_query.push(c);
+                self.update_search();
+            }
+            _ => (),
+        }
+    }
+
+    fn update_search(&mut self) {
+        if self.search_query.is_empty() {
+            self.current_match = None;
+            if let Some(origin) = &self.search_origin {
+                self.cursor = origin.clone();
+            }
+            return;
+        }
+
+        let origin = self
+            .search_origin
+            .clone()
+            .unwrap_or_else(|| self.cursor.clone());
+        if let Some(found) = self
+            .document
+            .find(&self.search_query, &origin, Direction::Forward)
+        {
+            self.cursor = found.clone();
+            self.current_match = Some(found);
+        }
+    }
+
+    fn handle_command_keypress(&mut self, key: Key) {
+        match key {
+            Key::Char('\n') => {
+                let command = self.command_buffer.clone();
+                self.command_buffer.clear();
+                self.mode = Mode::Normal;
+                self.execute_command(&command);
+            }
+            Key::Esc => {
+                self.command_buffer.clear();
+                self.mode = Mode::Normal;
+            }
+            Key::Backspace => {
+                self.command_buffer.pop();
+            }
+            Key::Char(c) => self.command_buffer.push(c),
+            _ => (),
+        }
+    }
+
+    fn execute_command(&mut self, command: &str) {
+        let command = command.trim();
+        let mut parts = command.splitn(2, ' ');
+        let name = parts.next().unwrap_or("");
+        let arg = parts.next().map(str::trim).filter(|s| !s.is_empty());
+
+        match name {
+            "" => (),
+            "w" => {
+                self.command_save(arg);
+            }
+            "q" => self.command_quit(false),
+            "q!" => self.command_quit(true),
+            "wq" => {
+                if self.command_save(arg) {
+                    self.command_quit(false);
+                }
+            }
+            "e" => match arg {
+                Some(filename) => {
+                    self.document = Document::open(filename);
+                    self.cursor = Position::default();
+                    self.scroll_offset = Position::default();
+                    self.set_status_message(format!("\"{}\" opened", filename));
+                }
+                None => self.set_status_message("E: :e requires a filename".to_string()),
+            },
+            _ => self.set_status_message(format!("E: unknown command \":{}\"", command)),
+        }
+    }
+
+    fn command_save(&mut self, filename: Option<&str>) -> bool {
+        if filename.is_none() && self.document.get_filename().is_none() {
+            self.set_status_message("E: no file name".to_string());
+            return false;
+        }
+
+        let result = match filename {
+            Some(name) => self.document.save_as(name),
+            None => self.document.save(),
+        };
+
+        match result {
+            Ok(()) => {
+                let filename = self.document.get_filename().unwrap_or_default();
+                self.set_status_message(format!("\"{}\" {} lines written", filename, self.document.len()));
+                true
+            }
+            Err(e) => {
+                self.set_status_message(format!("E: {}", e));
+                false
+            }
+        }
+    }
+
+    fn command_quit(&mut self, force: bool) {
+        if !force && self.document.is_dirty() {
+            self.set_status_message("E: unsaved changes (add ! to override, e.g. :q!)".to_string());
+            return;
+        }
+        self.quit = true;
+    }
+
+    fn set_status_message(&mut self, text: String) {
+        self.status_message = Some(StatusMessage::new(text));
+    }
+
+    fn jump_to_match(&mut self, direction: Direction) {
+        if self.search_query.is_empty() {
+            return;
+        }
+
+        let mut after = self.cursor.clone();
+        match direction {
+            Direction::Forward => after.x = after.x.saturating_add(1),
+            Direction::Backward => after.x = after.x.saturating_sub(1),
+        }
+        if let Some(found) = self.document.find(&self.search_query, &after, direction) {
+            self.cursor = found.clone();
+            self.current_match = Some(found);
+        }
+    }
+
     fn move_cursor(&mut self, key: Key) {
         let terminal_height = self.terminal.size().height as usize;
         let Position { mut x, mut y } = self.cursor;
@@ -147,11 +364,23 @@ impl Editor {
         };
 
         match key {
-            Key::Up => y = y.saturating_sub(1),
-            Key::Down => {
-                if y < height {
-                    y = y.saturating_add(1);
-                }
+            Key::Up | Key::Down => {
+                // Preserve the cursor's visual column (not its grapheme
+                // index) across rows of differing width, so moving through
+                // a wide CJK/emoji grapheme doesn't jog the cursor sideways.
+                let column = self
+                    .document
+                    .line(y)
+                    .map_or(0, |row| row.grapheme_to_column(x));
+                y = match key {
+                    Key::Up => y.saturating_sub(1),
+                    Key::Down if y < height => y.saturating_add(1),
+                    _ => y,
+                };
+                x = self
+                    .document
+                    .line(y)
+                    .map_or(0, |row| row.column_to_grapheme(column));
             }
             Key::Left => {
                 if x > 0 {
@@ -204,9 +433,18 @@ impl Editor {
         self.cursor = Position { x, y }
     }
 
+    /// The cursor's display column on its row, accounting for wide graphemes
+    /// that occupy more than one terminal cell.
+    fn cursor_column(&self) -> usize {
+        self.document
+            .line(self.cursor.y)
+            .map_or(0, |row| row.grapheme_to_column(self.cursor.x))
+    }
+
     fn scroll(&mut self) {
         // creating descriptive vars for term dimensions & cursor and offset pos
-        let Position { x, y } = self.cursor;
+        let y = self.cursor.y;
+        let column = self.cursor_column();
         let width = self.terminal.size().width as usize;
         let height = self.terminal.size().height as usize;
         let offset = &mut self.scroll_offset;
@@ -222,10 +460,10 @@ impl Editor {
         } else if y >= window_end_y.saturating_sub(SCROLLOFF) {
             window_start_y = y.saturating_sub(height).saturating_add(SCROLLOFF + 1);
         }
-        if x < window_start_x {
-            window_start_x = x;
-        } else if x >= window_end_x {
-            window_start_x = x.saturating_sub(width).saturating_add(1);
+        if column < window_start_x {
+            window_start_x = column;
+        } else if column >= window_end_x {
+            window_start_x = column.saturating_sub(width).saturating_add(1);
         }
 
         self.scroll_offset = Position {
@@ -234,37 +472,84 @@ impl Editor {
         };
     }
 
-    fn draw_line(&self, line: &Row, line_num: usize) {
+    fn active_match_on(&self, line_num: usize) -> Option<(usize, usize)> {
+        let position = self.current_match.as_ref()?;
+        if position.y != line_num || self.search_query.is_empty() {
+            return None;
+        }
+        let match_len = self.search_query.graphemes(true).count();
+        Some((position.x, position.x.saturating_add(match_len)))
+    }
+
+    /// Builds the full frame (content rows + status bar) into `self.screen`.
+    /// `Screen::present` is responsible for only writing the rows that
+    /// actually changed since the last frame.
+    fn build_frame(&mut self) {
+        if self.document.is_empty() {
+            self.build_homepage();
+        } else {
+            self.build_lines();
+        }
+        let status_row = self.terminal.size().height as usize;
+        let status = self.render_status_bar();
+        self.screen.set_row(status_row, status);
+
+        if let Some(message) = &self.status_message {
+            if message.time.elapsed() >= STATUS_MESSAGE_DURATION {
+                self.status_message = None;
+            }
+        }
+        let message_row = status_row.saturating_add(1);
+        let message = self.render_message_line();
+        self.screen.set_row(message_row, message);
+    }
+
+    fn build_lines(&mut self) {
+        let height = self.terminal.size().height as usize;
+        for terminal_line in 0..height {
+            let line_num = self.scroll_offset.y.saturating_add(terminal_line);
+            let content = if let Some(line) = self.document.line(line_num) {
+                self.render_line(line, line_num)
+            } else {
+                self.render_empty_line()
+            };
+            self.screen.set_row(terminal_line, content);
+        }
+    }
+
+    fn render_line(&self, line: &Row, line_num: usize) -> String {
         let width = self.terminal.size().width as usize;
         let line_num_width = self.document.len().to_string().len();
         let start = self.scroll_offset.x;
         let end = start.saturating_add(width - line_num_width);
-        let line = line.render(start, end);
+        let rendered = line.render(start, end, &self.theme, self.active_match_on(line_num));
+
         let mut line_num = line_num.saturating_add(1).to_string();
         while line_num.len() != line_num_width {
             line_num.insert(0, ' ');
         }
-        println!("{}{}\r", line_num, line);
-    }
 
-    fn draw_lines(&self) {
-        let height = self.terminal.size().height as usize;
-        if self.document.is_empty() {
-            self.draw_homepage();
-            return;
+        let mut out = String::new();
+        if let Some(fg) = self.theme.line_number.fg {
+            out.push_str(&format!("{}", color::Fg(fg.to_rgb())));
         }
-        for terminal_line in 0..height {
-            Terminal::clear_current_line();
-            let line_num = self.scroll_offset.y.saturating_add(terminal_line);
-            if let Some(line) = self.document.line(line_num) {
-                self.draw_line(line, line_num);
-            } else {
-                println!("~\r");
-            }
+        out.push_str(&line_num);
+        out.push_str(&format!("{}", color::Fg(color::Reset)));
+        out.push_str(&rendered);
+        out
+    }
+
+    fn render_empty_line(&self) -> String {
+        let mut out = String::new();
+        if let Some(fg) = self.theme.empty_line.fg {
+            out.push_str(&format!("{}", color::Fg(fg.to_rgb())));
         }
+        out.push('~');
+        out.push_str(&format!("{}", color::Fg(color::Reset)));
+        out
     }
 
-    fn draw_status_bar(&self) {
+    fn render_status_bar(&self) -> String {
         let mut status: String;
         let width = self.terminal.size().width as usize;
 
@@ -272,13 +557,19 @@ impl Editor {
             Mode::Normal => " N ",
             Mode::Insert => " I ",
             Mode::Command => " C ",
+            Mode::Search => " / ",
         };
-        let modified_indicator = if self.document.is_dirty() { " [!]" } else { "" };
-        let mut filename = "".to_string();
-        if let Some(name) = &self.document.get_filename() {
-            filename = name.clone();
+
+        if self.mode == Mode::Search {
+            status = format!("{}{}", mode_indicator, self.search_query);
+        } else {
+            let modified_indicator = if self.document.is_dirty() { " [!]" } else { "" };
+            let mut filename = "".to_string();
+            if let Some(name) = &self.document.get_filename() {
+                filename = name.clone();
+            }
+            status = format!("{}{}{}", mode_indicator, filename, modified_indicator);
         }
-        status = format!("{}{}{}", mode_indicator, filename, modified_indicator);
 
         let line_num_width = self.document.len().to_string().len();
         let mut line_num = self.cursor.y.to_string();
@@ -297,24 +588,35 @@ impl Editor {
         status = format!("{}{}", status, line_indicator);
         status.truncate(width);
 
-        Terminal::set_bg_color(STATUS_BG_COLOR);
-        Terminal::set_fg_color(STATUS_FG_COLOR);
-        println!("{}\r", status);
-        Terminal::reset_fg_color();
-        Terminal::reset_bg_color();
+        let mut out = String::new();
+        if let Some(bg) = self.theme.status.bg {
+            out.push_str(&format!("{}", color::Bg(bg.to_rgb())));
+        }
+        if let Some(fg) = self.theme.status.fg {
+            out.push_str(&format!("{}", color::Fg(fg.to_rgb())));
+        }
+        out.push_str(&status);
+        out.push_str(&format!("{}{}", color::Fg(color::Reset), color::Bg(color::Reset)));
+        out
     }
 
-    fn draw_homepage(&self) {
-        let mut title = format!("{}", EDITOR_NAME);
-        let mut version = format!("{}", VERSION);
-        let mut author = format!("{}", AUTHOR);
-        let num_lines = 4; // title + blank line + version + author
-
-        let width = self.terminal.size().width as usize;
+    /// The line below the status bar: the live command buffer while in
+    /// Command mode, otherwise the most recent status message (if it
+    /// hasn't yet expired).
+    fn render_message_line(&self) -> String {
+        if self.mode == Mode::Command {
+            return format!(":{}", self.command_buffer);
+        }
+        self.status_message
+            .as_ref()
+            .map_or(String::new(), |message| message.text.clone())
+    }
 
-        title = format!("~{}\r", center_text(title, width.saturating_sub(1)));
-        version = format!("~{}\r", center_text(version, width.saturating_sub(1)));
-        author = format!("~{}\r", center_text(author, width.saturating_sub(1)));
+    fn build_homepage(&mut self) {
+        let title = format!("~{}", center_text(EDITOR_NAME.to_string(), self.terminal.size().width.saturating_sub(1) as usize));
+        let version = format!("~{}", center_text(VERSION.to_string(), self.terminal.size().width.saturating_sub(1) as usize));
+        let author = format!("~{}", center_text(AUTHOR.to_string(), self.terminal.size().width.saturating_sub(1) as usize));
+        let num_lines = 4; // title + blank line + version + author
 
         let height = self.terminal.size().height as usize;
         let message_start_line = (height / 2).saturating_sub(num_lines / 2);
@@ -323,17 +625,21 @@ impl Editor {
         while terminal_line < height {
             let line_num = self.scroll_offset.y.saturating_add(terminal_line);
             if let Some(line) = self.document.line(line_num) {
-                self.draw_line(line, line_num);
+                let content = self.render_line(line, line_num);
+                self.screen.set_row(terminal_line, content);
+                terminal_line += 1;
             } else if terminal_line == message_start_line {
-                println!("{}\r", title);
-                println!("~\r");
-                println!("{}\r", version);
-                println!("{}\r", author);
-                terminal_line += 3; // 3 extra lines vs the other arms of the if
+                self.screen.set_row(terminal_line, title.clone());
+                let empty = self.render_empty_line();
+                self.screen.set_row(terminal_line + 1, empty);
+                self.screen.set_row(terminal_line + 2, version.clone());
+                self.screen.set_row(terminal_line + 3, author.clone());
+                terminal_line += 4;
             } else {
-                println!("~\r");
+                let content = self.render_empty_line();
+                self.screen.set_row(terminal_line, content);
+                terminal_line += 1;
             }
-            terminal_line += 1;
         }
     }
 }
@@ -0,0 +1,93 @@
+/*
+ * highlight.rs contains the source code for jot's syntax highlighting.
+ *
+ * A Highlighter is built once per document from the file extension and holds
+ * an ordered set of regexes, each tagged with the HighlightKind it marks.
+ * Rows scan their text against these patterns to produce a highlight vector
+ * consulted by Row::render.
+ */
+
+use crate::theme::{Theme, ThemeColor};
+use regex::Regex;
+use termion::color;
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightKind {
+    Normal,
+    Keyword,
+    String,
+    Number,
+    Comment,
+}
+
+impl HighlightKind {
+    pub fn fg(self, theme: &Theme) -> Option<color::Rgb> {
+        match self {
+            Self::Normal => None,
+            Self::Keyword => theme.highlight.keyword.map(ThemeColor::to_rgb),
+            Self::String => theme.highlight.string.map(ThemeColor::to_rgb),
+            Self::Number => theme.highlight.number.map(ThemeColor::to_rgb),
+            Self::Comment => theme.highlight.comment.map(ThemeColor::to_rgb),
+        }
+    }
+}
+
+pub struct Highlighter {
+    // Order matters: earlier patterns win on overlapping matches.
+    patterns: Vec<(Regex, HighlightKind)>,
+}
+
+impl Highlighter {
+    pub fn for_extension(extension: &str) -> Self {
+        let patterns = match extension {
+            "rs" => vec![
+                (Regex::new(r"//.*").unwrap(), HighlightKind::Comment),
+                (
+                    Regex::new(r#""(?:[^"\\]|\\.)*""#).unwrap(),
+                    HighlightKind::String,
+                ),
+                (
+                    Regex::new(r"\b\d+(?:\.\d+)?\b").unwrap(),
+                    HighlightKind::Number,
+                ),
+                (
+                    Regex::new(
+                        r"\b(fn|let|mut|if|else|match|for|while|loop|struct|enum|impl|trait|pub|use|mod|return|break|continue|self|Self|true|false)\b",
+                    )
+                    .unwrap(),
+                    HighlightKind::Keyword,
+                ),
+            ],
+            _ => Vec::new(),
+        };
+        Self { patterns }
+    }
+
+    /// Scans `text` and returns a HighlightKind per grapheme, matching the
+    /// grapheme-index convention Row already uses for Position.x.
+    pub fn highlight(&self, text: &str) -> Vec<HighlightKind> {
+        let grapheme_starts: Vec<usize> = text.grapheme_indices(true).map(|(i, _)| i).collect();
+        let mut kinds = vec![HighlightKind::Normal; grapheme_starts.len()];
+
+        for (regex, kind) in &self.patterns {
+            for matched in regex.find_iter(text) {
+                let start = byte_to_grapheme_index(&grapheme_starts, matched.start());
+                let end = byte_to_grapheme_index(&grapheme_starts, matched.end());
+                for slot in kinds.iter_mut().take(end).skip(start) {
+                    if *slot == HighlightKind::Normal {
+                        *slot = *kind;
+                    }
+                }
+            }
+        }
+
+        kinds
+    }
+}
+
+fn byte_to_grapheme_index(grapheme_starts: &[usize], byte_index: usize) -> usize {
+    grapheme_starts
+        .binary_search(&byte_index)
+        .unwrap_or_else(|insert_at| insert_at)
+}
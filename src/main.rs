@@ -6,13 +6,19 @@
 
 mod document;
 mod editor;
+mod highlight;
 mod row;
+mod screen;
 mod terminal;
-pub use document::Document;
+mod theme;
+pub use document::{Direction, Document};
 use editor::Editor;
 pub use editor::Position;
+pub use highlight::Highlighter;
 pub use row::Row;
+pub use screen::Screen;
 pub use terminal::Terminal;
+pub use theme::Theme;
 
 fn main() {
     Editor::default().run();
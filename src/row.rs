@@ -1,26 +1,28 @@
 /*
  * row.rs contains the source code for a row of text in a document.
- *
- * TODO: coloring!! at minimum, every row needs a default background color and
- *       foreground color. Using terminal defaults is ok for now but eventually
- *       I need a theming module that parses a TOML file of colors at startup
- *       and use it in Row::render()
  */
 
+use crate::document::Direction;
+use crate::highlight::{HighlightKind, Highlighter};
+use crate::Theme;
 use std::cmp;
+use termion::color;
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Default)]
 pub struct Row {
     text: String,
     len: usize,
+    highlights: Vec<HighlightKind>,
 }
 
 impl From<&str> for Row {
     fn from(line: &str) -> Self {
         Self {
             text: line.to_string(),
-            len: line.len(),
+            len: line.graphemes(true).count(),
+            highlights: Vec::new(),
         }
     }
 }
@@ -38,7 +40,48 @@ impl Row {
         self.text.as_bytes()
     }
 
+    /// Total number of terminal columns this row occupies, accounting for
+    /// wide graphemes (CJK, emoji) that take more than one cell.
+    pub fn width(&self) -> usize {
+        self.text.graphemes(true).map(grapheme_width).sum()
+    }
+
+    fn column_starts(&self) -> Vec<usize> {
+        let mut starts = Vec::with_capacity(self.len + 1);
+        let mut column = 0;
+        for grapheme in self.text.graphemes(true) {
+            starts.push(column);
+            column += grapheme_width(grapheme);
+        }
+        starts.push(column);
+        starts
+    }
+
+    /// Display column at which the grapheme at `index` begins.
+    pub fn grapheme_to_column(&self, index: usize) -> usize {
+        let starts = self.column_starts();
+        starts[index.min(starts.len() - 1)]
+    }
+
+    /// Grapheme index occupying display column `column`, clamped to `len`.
+    pub fn column_to_grapheme(&self, column: usize) -> usize {
+        let starts = self.column_starts();
+        match starts.binary_search(&column) {
+            Ok(index) => index.min(self.len),
+            Err(index) => index.saturating_sub(1).min(self.len),
+        }
+    }
+
+    pub fn highlight(&mut self, highlighter: &Highlighter) {
+        self.highlights = highlighter.highlight(&self.text);
+    }
+
+    fn invalidate_highlight(&mut self) {
+        self.highlights.clear();
+    }
+
     pub fn insert(&mut self, at: usize, c: char) {
+        self.invalidate_highlight();
         if at >= self.len {
             self.text.push(c);
             self.len += 1;
@@ -62,6 +105,7 @@ impl Row {
     }
 
     pub fn delete(&mut self, at: usize) {
+        self.invalidate_highlight();
         if at >= self.len {
             return;
         }
@@ -78,6 +122,7 @@ impl Row {
     }
 
     pub fn split(&mut self, at: usize) -> Self {
+        self.invalidate_highlight();
         let mut curr_line = String::new();
         let mut curr_len = 0;
         let mut new_line = String::new();
@@ -98,30 +143,130 @@ impl Row {
         Self {
             text: new_line,
             len: new_len,
+            highlights: Vec::new(),
         }
     }
 
     pub fn append(&mut self, new_row: &Self) {
+        self.invalidate_highlight();
         self.text = format!("{}{}", self.text, new_row.text);
         self.len += new_row.len;
     }
 
-    pub fn render(&self, start: usize, end: usize) -> String {
-        let end = cmp::min(end, self.len);
+    /// Finds `query` starting from grapheme index `at`, consistent with the
+    /// grapheme-index convention `Position.x` already uses.
+    pub fn find(&self, query: &str, at: usize, direction: Direction) -> Option<usize> {
+        if query.is_empty() {
+            return None;
+        }
+
+        let grapheme_starts: Vec<usize> = self.text.grapheme_indices(true).map(|(i, _)| i).collect();
+        let at = at.min(grapheme_starts.len());
+
+        match direction {
+            Direction::Forward => {
+                let start_byte = grapheme_starts.get(at).copied().unwrap_or(self.text.len());
+                self.text[start_byte..]
+                    .find(query)
+                    .map(|offset| byte_to_grapheme_index(&grapheme_starts, start_byte + offset))
+            }
+            Direction::Backward => {
+                let end_byte = grapheme_starts.get(at).copied().unwrap_or(self.text.len());
+                self.text[..end_byte]
+                    .rfind(query)
+                    .map(|offset| byte_to_grapheme_index(&grapheme_starts, offset))
+            }
+        }
+    }
+
+    /// Renders the terminal-column window `start..end`, with `active_match`
+    /// (a grapheme range on this row) drawn in the theme's search-match
+    /// colors in place of its normal highlight. A grapheme only half inside
+    /// the window (a wide CJK/emoji cluster straddling an edge) is dropped
+    /// and padded with blanks for its visible cells instead of being drawn.
+    pub fn render(
+        &self,
+        start: usize,
+        end: usize,
+        theme: &Theme,
+        active_match: Option<(usize, usize)>,
+    ) -> String {
+        let window_width = end.saturating_sub(start);
+        let end = cmp::min(end, self.width());
         let start = cmp::min(start, end);
         let mut rendered_string = String::new();
-        // TODO: index will be useful for highlighting
-        for (__index, grapheme) in self.text[..]
-            .graphemes(true)
-            .enumerate()
-            .skip(start)
-            .take(end - start)
-        {
-            if let Some(c) = grapheme.chars().next() {
-                rendered_string.push(c);
+        if let Some(bg) = theme.text.bg {
+            rendered_string.push_str(&format!("{}", color::Bg(bg.to_rgb())));
+        }
+
+        let mut visible_width = 0;
+        let mut column = 0;
+        let mut current_style: Option<(HighlightKind, bool)> = None;
+        for (index, grapheme) in self.text[..].graphemes(true).enumerate() {
+            if column >= end {
+                break;
+            }
+            let grapheme_start = column;
+            let grapheme_end = column + grapheme_width(grapheme);
+            column = grapheme_end;
+            if grapheme_end <= start {
+                continue;
+            }
+
+            let kind = self.highlights.get(index).copied().unwrap_or(HighlightKind::Normal);
+            let is_match =
+                active_match.map_or(false, |(match_start, match_end)| index >= match_start && index < match_end);
+            let style = (kind, is_match);
+            if current_style != Some(style) {
+                if is_match {
+                    match theme.search_match.bg {
+                        Some(bg) => rendered_string.push_str(&format!("{}", color::Bg(bg.to_rgb()))),
+                        None => rendered_string.push_str(&format!("{}", color::Bg(color::Reset))),
+                    }
+                    match theme.search_match.fg {
+                        Some(fg) => rendered_string.push_str(&format!("{}", color::Fg(fg.to_rgb()))),
+                        None => rendered_string.push_str(&format!("{}", color::Fg(color::Reset))),
+                    }
+                } else {
+                    match theme.text.bg {
+                        Some(bg) => rendered_string.push_str(&format!("{}", color::Bg(bg.to_rgb()))),
+                        None => rendered_string.push_str(&format!("{}", color::Bg(color::Reset))),
+                    }
+                    match kind.fg(theme).or_else(|| theme.text.fg.map(|fg| fg.to_rgb())) {
+                        Some(fg) => rendered_string.push_str(&format!("{}", color::Fg(fg))),
+                        None => rendered_string.push_str(&format!("{}", color::Fg(color::Reset))),
+                    }
+                }
+                current_style = Some(style);
             }
+
+            let visible_cols = cmp::min(grapheme_end, end).saturating_sub(cmp::max(grapheme_start, start));
+            if grapheme_start < start || grapheme_end > end {
+                // Half-visible wide grapheme at a window edge: pad instead.
+                rendered_string.push_str(&" ".repeat(visible_cols));
+            } else {
+                rendered_string.push_str(grapheme);
+            }
+            visible_width += visible_cols;
+        }
+
+        if theme.text.bg.is_some() {
+            // Paint the background all the way to the right edge of the
+            // window instead of leaving the remainder as terminal default.
+            rendered_string.push_str(&" ".repeat(window_width.saturating_sub(visible_width)));
         }
 
+        rendered_string.push_str(&format!("{}{}", color::Fg(color::Reset), color::Bg(color::Reset)));
         rendered_string
     }
 }
+
+fn grapheme_width(grapheme: &str) -> usize {
+    UnicodeWidthStr::width(grapheme).max(1)
+}
+
+fn byte_to_grapheme_index(grapheme_starts: &[usize], byte_index: usize) -> usize {
+    grapheme_starts
+        .binary_search(&byte_index)
+        .unwrap_or_else(|insert_at| insert_at)
+}
@@ -0,0 +1,61 @@
+/*
+ * screen.rs contains the source code for jot's back-buffer frame rendering.
+ *
+ * Screen holds the most recently presented frame (one String per terminal
+ * row) and diffs each new frame against it, so presenting only emits
+ * cursor-move + clear-to-EOL + content for rows that actually changed
+ * instead of reprinting every row on every keystroke.
+ */
+
+use std::io::{self, Write};
+use termion::{clear, cursor};
+
+pub struct Screen {
+    rows: Vec<String>,
+    previous: Vec<String>,
+}
+
+impl Screen {
+    pub fn new(rows: usize) -> Self {
+        Self {
+            rows: vec![String::new(); rows],
+            previous: Vec::new(),
+        }
+    }
+
+    /// Resets the buffer to `rows` blank lines and forces the next
+    /// `present` to redraw every row (used after a terminal resize).
+    pub fn resize(&mut self, rows: usize) {
+        self.rows = vec![String::new(); rows];
+        self.previous.clear();
+    }
+
+    pub fn set_row(&mut self, index: usize, content: String) {
+        if let Some(row) = self.rows.get_mut(index) {
+            *row = content;
+        }
+    }
+
+    pub fn present(&mut self) -> io::Result<()> {
+        let mut out = String::new();
+        for (index, row) in self.rows.iter().enumerate() {
+            if self.previous.get(index) != Some(row) {
+                out.push_str(&format!(
+                    "{}{}{}",
+                    cursor::Goto(1, index as u16 + 1),
+                    clear::CurrentLine,
+                    row
+                ));
+            }
+        }
+
+        if !out.is_empty() {
+            let mut stdout = io::stdout();
+            stdout.write_all(out.as_bytes())?;
+            stdout.flush()?;
+        }
+
+        self.previous = self.rows.clone();
+        Ok(())
+    }
+}
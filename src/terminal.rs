@@ -0,0 +1,87 @@
+/*
+ * terminal.rs contains the source code for jot's interface to the raw
+ * terminal: entering/leaving raw mode, reading keys, and the handful of
+ * low-level cursor/screen control sequences the editor emits around each frame.
+ */
+
+use crate::Position;
+use std::io::{self, stdout, Stdout, Write};
+use termion::cursor;
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::{IntoRawMode, RawTerminal};
+
+pub struct Size {
+    pub width: u16,
+    pub height: u16,
+}
+
+pub struct Terminal {
+    size: Size,
+    _stdout: RawTerminal<Stdout>,
+}
+
+impl Terminal {
+    pub fn default() -> Result<Self, io::Error> {
+        let size = termion::terminal_size()?;
+        Ok(Self {
+            size: Size {
+                width: size.0,
+                // Leave two rows: one for the status bar, one for the
+                // command/message line below it.
+                height: size.1.saturating_sub(2),
+            },
+            _stdout: stdout().into_raw_mode()?,
+        })
+    }
+
+    pub fn size(&self) -> &Size {
+        &self.size
+    }
+
+    /// Re-queries the terminal's current dimensions and updates the cached
+    /// `Size` if they changed (eg. the window was resized). Returns whether
+    /// the size actually changed, so callers know when to force a repaint.
+    pub fn refresh_size(&mut self) -> Result<bool, io::Error> {
+        let size = termion::terminal_size()?;
+        let height = size.1.saturating_sub(2);
+        if size.0 != self.size.width || height != self.size.height {
+            self.size = Size {
+                width: size.0,
+                height,
+            };
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    pub fn clear_screen() {
+        print!("{}", termion::clear::All);
+    }
+
+    pub fn cursor_position(position: &Position) {
+        let x = position.x.saturating_add(1) as u16;
+        let y = position.y.saturating_add(1) as u16;
+        print!("{}", cursor::Goto(x, y));
+    }
+
+    pub fn cursor_visible(visible: bool) {
+        if visible {
+            print!("{}", cursor::Show);
+        } else {
+            print!("{}", cursor::Hide);
+        }
+    }
+
+    pub fn flush() -> Result<(), io::Error> {
+        io::stdout().flush()
+    }
+
+    pub fn read_key() -> Result<Key, io::Error> {
+        loop {
+            if let Some(key) = io::stdin().lock().keys().next() {
+                return key;
+            }
+        }
+    }
+}
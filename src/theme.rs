@@ -0,0 +1,155 @@
+/*
+ * theme.rs contains the source code for jot's color theming.
+ *
+ * A theme is loaded from a TOML file (by default `~/.config/jot/theme.toml`)
+ * at startup. Any section or color left out of the file, or any file that
+ * fails to parse, falls back to its default so jot is always usable without
+ * a config: terminal defaults for body text, line numbers and `~` markers,
+ * and built-in colors for the status bar, syntax highlighting and the active
+ * search match.
+ */
+
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use termion::color;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ThemeColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl ThemeColor {
+    pub fn to_rgb(self) -> color::Rgb {
+        color::Rgb(self.r, self.g, self.b)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct TextColors {
+    pub fg: Option<ThemeColor>,
+    pub bg: Option<ThemeColor>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StatusColors {
+    pub fg: Option<ThemeColor>,
+    pub bg: Option<ThemeColor>,
+}
+
+impl Default for StatusColors {
+    fn default() -> Self {
+        // Matches the hardcoded STATUS_FG_COLOR/STATUS_BG_COLOR this theme replaces.
+        Self {
+            fg: Some(ThemeColor { r: 136, g: 0, b: 26 }),
+            bg: Some(ThemeColor {
+                r: 230,
+                g: 233,
+                b: 236,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct LineNumberColors {
+    pub fg: Option<ThemeColor>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct EmptyLineColors {
+    pub fg: Option<ThemeColor>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HighlightColors {
+    pub keyword: Option<ThemeColor>,
+    pub string: Option<ThemeColor>,
+    pub number: Option<ThemeColor>,
+    pub comment: Option<ThemeColor>,
+}
+
+impl Default for HighlightColors {
+    fn default() -> Self {
+        Self {
+            keyword: Some(ThemeColor {
+                r: 97,
+                g: 175,
+                b: 239,
+            }),
+            string: Some(ThemeColor {
+                r: 152,
+                g: 195,
+                b: 121,
+            }),
+            number: Some(ThemeColor {
+                r: 209,
+                g: 154,
+                b: 102,
+            }),
+            comment: Some(ThemeColor {
+                r: 92,
+                g: 99,
+                b: 112,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MatchColors {
+    pub fg: Option<ThemeColor>,
+    pub bg: Option<ThemeColor>,
+}
+
+impl Default for MatchColors {
+    fn default() -> Self {
+        Self {
+            fg: Some(ThemeColor { r: 0, g: 0, b: 0 }),
+            bg: Some(ThemeColor {
+                r: 255,
+                g: 213,
+                b: 79,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct Theme {
+    pub text: TextColors,
+    pub status: StatusColors,
+    pub line_number: LineNumberColors,
+    pub empty_line: EmptyLineColors,
+    pub highlight: HighlightColors,
+    pub search_match: MatchColors,
+}
+
+impl Theme {
+    /// Loads the theme at `~/.config/jot/theme.toml`, falling back to
+    /// `Theme::default()` when the file is missing or malformed.
+    pub fn load() -> Self {
+        Self::load_from(config_path())
+    }
+
+    fn load_from(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    let home = env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".config/jot/theme.toml"))
+}